@@ -4,6 +4,7 @@ use actix_web_httpauth::extractors::bearer::{BearerAuth, Config};
 use actix_web_httpauth::extractors::AuthenticationError;
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use super::Context;
 
@@ -11,15 +12,41 @@ use super::Context;
 struct Claims {
     sub: String,
     company: String,
+    scopes: Vec<String>,
     exp: usize,
 }
 
+/// The scope that matches every project, used for tokens minted without an
+/// explicit `scopes` list so existing unscoped callers keep working.
+const UNSCOPED: &str = "*";
+
+/// A year-long default for callers that don't pass an explicit expiry.
+const DEFAULT_TOKEN_TTL: Duration = Duration::from_secs(60 * 60 * 24 * 365);
+
+/// Mints an unscoped token valid for every project, expiring after
+/// `DEFAULT_TOKEN_TTL`. Prefer `create_scoped_token` to narrow a token to
+/// the project(s) it should actually be able to deploy.
 pub fn create_token(secret: &str) -> Result<String, jsonwebtoken::errors::Error> {
+    create_scoped_token(secret, vec![UNSCOPED.to_owned()], DEFAULT_TOKEN_TTL)
+}
+
+pub fn create_scoped_token(
+    secret: &str,
+    scopes: Vec<String>,
+    expires_in: Duration,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    let exp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .checked_add(expires_in)
+        .unwrap_or_default()
+        .as_secs() as usize;
+
     let claims = Claims {
         sub: "thresh:agent".to_owned(),
         company: "thresh".to_owned(),
-        // TODO: Move exp to configuration
-        exp: 10_000_000_000,
+        scopes,
+        exp,
     };
 
     let mut header = Header::default();
@@ -32,6 +59,18 @@ pub fn create_token(secret: &str) -> Result<String, jsonwebtoken::errors::Error>
     )
 }
 
+pub(crate) fn authenticate(
+    secret: &str,
+    token: &str,
+) -> Result<Vec<String>, jsonwebtoken::errors::Error> {
+    decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &Validation::new(Algorithm::HS512),
+    )
+    .map(|data| data.claims.scopes)
+}
+
 pub async fn validator(
     req: ServiceRequest,
     credentials: BearerAuth,
@@ -44,13 +83,16 @@ pub async fn validator(
         .unwrap_or_else(Default::default);
 
     let token = credentials.token();
-    let token_result = decode::<Claims>(
-        &token,
-        &DecodingKey::from_secret(&context.secret.as_bytes()),
-        &Validation::new(Algorithm::HS512),
-    );
+    let scopes = authenticate(&context.secret, token)
+        .map_err(|_| Error::from(AuthenticationError::from(config.clone())))?;
+
+    if let Some(project) = req.match_info().get("project") {
+        if !scopes.iter().any(|scope| scope == UNSCOPED || scope == project) {
+            return Err(actix_web::error::ErrorForbidden(
+                "token is not scoped for this project",
+            ));
+        }
+    }
 
-    token_result
-        .map(|_| req)
-        .map_err(|_| AuthenticationError::from(config).into())
+    Ok(req)
 }
\ No newline at end of file
@@ -0,0 +1,144 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+use tokio::time;
+
+use crate::auth;
+use crate::job::queue;
+use crate::job::Witness;
+use crate::protocol::{apply_to_witness, Message};
+use crate::Context;
+use crate::SubiloError;
+
+/// How often the driver pings a connected runner, so a runner that died
+/// mid-job is noticed even while no job output is flowing.
+const PING_INTERVAL: Duration = Duration::from_secs(15);
+
+pub async fn listen(bind_addr: &str, context: Context) -> Result<(), SubiloError> {
+    let listener = TcpListener::bind(bind_addr)
+        .await
+        .map_err(|err| SubiloError::DriverBind { source: err })?;
+
+    loop {
+        let (stream, _) = listener
+            .accept()
+            .await
+            .map_err(|err| SubiloError::DriverAccept { source: err })?;
+
+        let context = context.clone();
+        tokio::spawn(async move {
+            if let Err(err) = handle_runner(stream, context).await {
+                eprintln!("runner connection error: {}", err);
+            }
+        });
+    }
+}
+
+async fn handle_runner(stream: TcpStream, context: Context) -> Result<(), SubiloError> {
+    let (read_half, write_half) = stream.into_split();
+    let write_half = Arc::new(Mutex::new(write_half));
+    let mut lines = BufReader::new(read_half).lines();
+
+    let auth_line = lines
+        .next_line()
+        .await
+        .map_err(|err| SubiloError::DriverRead { source: err })?
+        .ok_or(SubiloError::RunnerDisconnected)?;
+
+    let token = auth_line.trim_start_matches("Bearer ").trim().to_owned();
+    auth::authenticate(&context.secret, &token).map_err(|_| SubiloError::Unauthorized)?;
+
+    loop {
+        let claimed = loop {
+            match queue::claim_next(&context).await {
+                Ok(Some(claimed)) => break claimed,
+                Ok(None) => time::sleep(Duration::from_secs(1)).await,
+                Err(err) => return Err(err),
+            }
+        };
+
+        let project = claimed.project.clone();
+        let commands = claimed.commands.clone();
+        let mut witness = Witness::resume(claimed, context.clone())?;
+
+        send(&write_half, &Message::StartJob { project, commands }).await?;
+
+        let ping_handle = {
+            let write_half = write_half.clone();
+            tokio::spawn(async move {
+                let mut ticker = time::interval(PING_INTERVAL);
+                loop {
+                    ticker.tick().await;
+                    if send(&write_half, &Message::Ping).await.is_err() {
+                        break;
+                    }
+                }
+            })
+        };
+
+        let mut disconnected = false;
+
+        loop {
+            let line = match lines
+                .next_line()
+                .await
+                .map_err(|err| SubiloError::DriverRead { source: err })
+            {
+                Ok(Some(line)) => line,
+                Ok(None) => {
+                    disconnected = true;
+                    break;
+                }
+                Err(err) => {
+                    ping_handle.abort();
+                    return Err(err);
+                }
+            };
+
+            let message = match Message::from_line(&line) {
+                Ok(message) => message,
+                Err(err) => {
+                    ping_handle.abort();
+                    return Err(SubiloError::RunnerParseMessage { source: err });
+                }
+            };
+
+            if matches!(message, Message::Pong) {
+                continue;
+            }
+
+            let finished = matches!(message, Message::JobFinished { .. });
+
+            if let Err(err) = apply_to_witness(&mut witness, message) {
+                ping_handle.abort();
+                return Err(err);
+            }
+
+            if finished {
+                break;
+            }
+        }
+
+        ping_handle.abort();
+
+        if disconnected {
+            return Ok(());
+        }
+    }
+}
+
+async fn send(write_half: &Arc<Mutex<OwnedWriteHalf>>, message: &Message) -> Result<(), SubiloError> {
+    let line = message
+        .to_line()
+        .map_err(|err| SubiloError::RunnerParseMessage { source: err })?;
+
+    write_half
+        .lock()
+        .await
+        .write_all(line.as_bytes())
+        .await
+        .map_err(|err| SubiloError::DriverWrite { source: err })
+}
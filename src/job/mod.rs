@@ -1,19 +1,32 @@
 use futures::executor::block_on;
+use futures::future::join_all;
 use nanoid::nanoid;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io::Write;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::time;
 
 use crate::core;
 use crate::database;
+use crate::notifier::{JobEvent, Notifier};
 use crate::Context;
 use crate::SubiloError;
 
 pub mod query;
+pub mod queue;
 
-#[derive(Debug, Deserialize, Serialize)]
+const LOG_TAIL_LINES: usize = 50;
+
+/// How often a live `Witness` refreshes `heartbeat_at`, so the orphan sweep
+/// never mistakes a job that is still running for a crashed worker.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum JobStatus {
+    Queued,
     Started,
     Succeeded,
     Failed,
@@ -44,12 +57,28 @@ pub struct Job {
     pub started_at: String,
     pub ended_at: String,
     pub commands: serde_json::Value,
+    pub error: Option<JobError>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct JobError {
+    pub command: String,
+    pub exit_code: Option<i32>,
+    pub signal_terminated: bool,
+    pub message: String,
 }
 
 pub struct Witness {
     id: String,
+    job_name: String,
+    project_name: String,
     log: std::fs::File,
+    log_path: String,
+    started_at: Instant,
+    current_command: Option<String>,
+    notifiers: Arc<Vec<Box<dyn Notifier>>>,
     context: Context,
+    heartbeat_handle: tokio::task::JoinHandle<()>,
 }
 
 impl Witness {
@@ -58,15 +87,6 @@ impl Witness {
         project: core::Project,
         context: Context,
     ) -> Result<Self, SubiloError> {
-        fs::create_dir_all(&context.logs_dir)
-            .map_err(|err| SubiloError::CreateLogDir { source: err })?;
-
-        let mut log = fs::File::create(create_log_name(&job_name, &context.logs_dir))
-            .map_err(|err| SubiloError::CreateLogFile { source: err })?;
-
-        log.write_all(&project.description().as_bytes())
-            .map_err(|err| SubiloError::WriteLogFile { source: err })?;
-
         let id = nanoid!();
         let status = JobStatus::Started.to_string().to_lowercase();
         let started_at = now();
@@ -81,10 +101,11 @@ impl Witness {
                 query: query::INSERT_JOB.to_owned(),
                 params: vec![
                     id.clone(),
-                    job_name,
+                    job_name.clone(),
                     status,
-                    project_name,
+                    project_name.clone(),
                     commands,
+                    started_at.clone(),
                     started_at,
                 ],
             })
@@ -92,10 +113,62 @@ impl Witness {
             .map_err(|err| SubiloError::DatabaseActor { source: err })?
             .map_err(|err| SubiloError::DatabaseQuery { source: err })?;
 
-        Ok(Self { id, context, log })
+        let mut witness = Self::attach(id, job_name, project_name, context)?;
+
+        witness
+            .log
+            .write_all(project.description().as_bytes())
+            .map_err(|err| SubiloError::WriteLogFile { source: err })?;
+
+        witness.emit(JobStatus::Started, None);
+
+        Ok(witness)
+    }
+
+    fn attach(
+        id: String,
+        job_name: String,
+        project_name: String,
+        context: Context,
+    ) -> Result<Self, SubiloError> {
+        fs::create_dir_all(&context.logs_dir)
+            .map_err(|err| SubiloError::CreateLogDir { source: err })?;
+
+        let log_path = create_log_name(&job_name, &context.logs_dir);
+        let log = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_path)
+            .map_err(|err| SubiloError::CreateLogFile { source: err })?;
+
+        let notifiers = context.notifiers.clone();
+        let heartbeat_handle = spawn_heartbeat(context.clone(), id.clone());
+
+        Ok(Self {
+            id,
+            job_name,
+            project_name,
+            log,
+            log_path,
+            started_at: Instant::now(),
+            current_command: None,
+            notifiers,
+            context,
+            heartbeat_handle,
+        })
+    }
+
+    pub fn resume(claimed: queue::ClaimedJob, context: Context) -> Result<Self, SubiloError> {
+        let witness = Self::attach(claimed.id, claimed.name, claimed.project, context)?;
+
+        witness.emit(JobStatus::Started, None);
+
+        Ok(witness)
     }
 
     pub fn report_command(&mut self, command: &str) -> Result<(), SubiloError> {
+        self.current_command = Some(command.to_owned());
+
         self.log
             .write_all(format!("$ {}\n", &command).as_bytes())
             .map_err(|err| SubiloError::WriteLogFile { source: err })
@@ -113,7 +186,11 @@ impl Witness {
         block_on(update_job)
             .map_err(|err| SubiloError::DatabaseActor { source: err })?
             .map_err(|err| SubiloError::DatabaseQuery { source: err })
-            .map(|_res| ())
+            .map(|_res| ())?;
+
+        self.emit(JobStatus::Succeeded, None);
+
+        Ok(())
     }
 
     pub fn report_command_error_by_code(
@@ -142,7 +219,17 @@ impl Witness {
         block_on(update_job)
             .map_err(|err| SubiloError::DatabaseActor { source: err })?
             .map_err(|err| SubiloError::DatabaseQuery { source: err })
-            .map(|_res| ())
+            .map(|_res| ())?;
+
+        let message = match status_code {
+            Some(code) => format!("Exit {}", code),
+            None => "Process terminated by signal".to_owned(),
+        };
+        self.insert_error(status_code, status_code.is_none(), message)?;
+
+        self.emit(JobStatus::Failed, status_code);
+
+        Ok(())
     }
 
     pub fn report_command_error(&mut self, err: core::RunError) -> Result<(), SubiloError> {
@@ -161,18 +248,136 @@ impl Witness {
         block_on(update_job)
             .map_err(|err| SubiloError::DatabaseActor { source: err })?
             .map_err(|err| SubiloError::DatabaseQuery { source: err })
-            .map(|_res| ())
+            .map(|_res| ())?;
+
+        self.insert_error(None, false, err.to_string())?;
+
+        self.emit(JobStatus::Failed, None);
+
+        Ok(())
     }
 
     pub fn try_clone_log(&self) -> Result<std::fs::File, std::io::Error> {
         self.log.try_clone()
     }
+
+    pub fn report_orphaned(&mut self) -> Result<(), SubiloError> {
+        self.log
+            .write_all(b"Job orphaned: heartbeat timed out\n")
+            .map_err(|err| SubiloError::WriteLogFile { source: err })?;
+
+        let ended_at = now();
+        let status = JobStatus::Failed.to_string().to_lowercase();
+
+        let update_job = self.context.database.send(database::Execute {
+            query: query::UPDATE_JOB.to_owned(),
+            params: vec![self.id.clone(), status, ended_at],
+        });
+
+        block_on(update_job)
+            .map_err(|err| SubiloError::DatabaseActor { source: err })?
+            .map_err(|err| SubiloError::DatabaseQuery { source: err })
+            .map(|_res| ())?;
+
+        self.insert_error(
+            None,
+            false,
+            "heartbeat timed out; job orphaned by a crashed worker".to_owned(),
+        )?;
+
+        self.emit(JobStatus::Failed, None);
+
+        Ok(())
+    }
+
+    fn insert_error(
+        &self,
+        exit_code: Option<i32>,
+        signal_terminated: bool,
+        message: String,
+    ) -> Result<(), SubiloError> {
+        let insert_error = self.context.database.send(database::Execute {
+            query: query::INSERT_ERROR.to_owned(),
+            params: vec![
+                nanoid!(),
+                self.id.clone(),
+                self.current_command.clone().unwrap_or_default(),
+                exit_code.map(|code| code.to_string()).unwrap_or_default(),
+                signal_terminated.to_string(),
+                message,
+                now(),
+            ],
+        });
+
+        block_on(insert_error)
+            .map_err(|err| SubiloError::DatabaseActor { source: err })?
+            .map_err(|err| SubiloError::DatabaseQuery { source: err })
+            .map(|_res| ())
+    }
+
+    fn emit(&self, status: JobStatus, exit_code: Option<i32>) {
+        if self.notifiers.is_empty() {
+            return;
+        }
+
+        let event = JobEvent {
+            job_id: self.id.clone(),
+            job_name: self.job_name.clone(),
+            project: self.project_name.clone(),
+            status,
+            duration_ms: self.started_at.elapsed().as_millis() as i64,
+            exit_code,
+            log_tail: read_log_tail(&self.log_path, LOG_TAIL_LINES),
+        };
+
+        let notifications = self.notifiers.iter().filter_map(|notifier| {
+            if notifier.notify_on().contains(&event.status) {
+                Some(notifier.notify(&event))
+            } else {
+                None
+            }
+        });
+
+        for result in block_on(join_all(notifications)) {
+            if let Err(err) = result {
+                eprintln!("failed to send job notification: {}", err);
+            }
+        }
+    }
+}
+
+impl Drop for Witness {
+    fn drop(&mut self) {
+        self.heartbeat_handle.abort();
+    }
+}
+
+fn spawn_heartbeat(context: Context, job_id: String) -> tokio::task::JoinHandle<()> {
+    actix::spawn(async move {
+        let mut ticker = time::interval(HEARTBEAT_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if let Err(err) = queue::heartbeat(&context, &job_id).await {
+                eprintln!("failed to record heartbeat: {}", err);
+            }
+        }
+    })
 }
 
 fn now() -> String {
     chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
 }
 
+fn read_log_tail(log_path: &str, n: usize) -> String {
+    fs::read_to_string(log_path)
+        .map(|contents| {
+            let lines: Vec<&str> = contents.lines().collect();
+            let start = lines.len().saturating_sub(n);
+            lines[start..].join("\n")
+        })
+        .unwrap_or_default()
+}
+
 pub fn create_log_name(job: &str, log_dir: &str) -> String {
     let log_dir = shellexpand::tilde(&log_dir).into_owned();
     format!("{}/{}.log", log_dir, job)
@@ -0,0 +1,139 @@
+pub const INSERT_JOB: &str = "
+INSERT INTO jobs (id, name, status, project, commands, started_at, heartbeat_at)
+VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+";
+
+pub const UPDATE_JOB: &str = "
+UPDATE jobs
+SET status = ?2, ended_at = ?3
+WHERE id = ?1
+";
+
+pub const CLAIM_NEXT_QUEUED_JOB: &str = "
+UPDATE jobs
+SET status = ?2, heartbeat_at = ?3
+WHERE id = (
+    SELECT id FROM jobs
+    WHERE status = ?1
+    ORDER BY started_at ASC
+    LIMIT 1
+)
+RETURNING id, name, project, commands
+";
+
+pub const UPDATE_HEARTBEAT: &str = "
+UPDATE jobs
+SET heartbeat_at = ?2
+WHERE id = ?1
+";
+
+pub const SELECT_ORPHANED_JOBS: &str = "
+SELECT id, name, project FROM jobs
+WHERE status = ?1 AND (heartbeat_at IS NULL OR heartbeat_at < ?2)
+";
+
+pub const INSERT_ERROR: &str = "
+INSERT INTO errors (id, job_id, command, exit_code, signal_terminated, message, created_at)
+VALUES (?1, ?2, ?3, NULLIF(?4, ''), ?5, ?6, ?7)
+";
+
+// LEFT JOINed against `errors` so every `Job` read path can surface the
+// failure cause alongside the row, instead of `failed` jobs needing a
+// separate lookup.
+pub const JOBS_WITH_ERRORS: &str = "
+SELECT
+    jobs.id, jobs.name, jobs.status, jobs.project, jobs.started_at, jobs.ended_at, jobs.commands,
+    errors.command AS error_command,
+    errors.exit_code AS error_exit_code,
+    errors.signal_terminated AS error_signal_terminated,
+    errors.message AS error_message
+FROM jobs
+LEFT JOIN errors ON errors.job_id = jobs.id
+ORDER BY jobs.started_at DESC
+";
+
+pub const JOB_WITH_ERRORS_BY_ID: &str = "
+SELECT
+    jobs.id, jobs.name, jobs.status, jobs.project, jobs.started_at, jobs.ended_at, jobs.commands,
+    errors.command AS error_command,
+    errors.exit_code AS error_exit_code,
+    errors.signal_terminated AS error_signal_terminated,
+    errors.message AS error_message
+FROM jobs
+LEFT JOIN errors ON errors.job_id = jobs.id
+WHERE jobs.id = ?1
+";
+
+#[derive(Debug, serde::Deserialize)]
+struct JobWithErrorRow {
+    id: String,
+    name: String,
+    status: String,
+    project: String,
+    started_at: String,
+    ended_at: String,
+    commands: serde_json::Value,
+    error_command: Option<String>,
+    error_exit_code: Option<i32>,
+    error_signal_terminated: Option<bool>,
+    error_message: Option<String>,
+}
+
+impl From<JobWithErrorRow> for super::Job {
+    fn from(row: JobWithErrorRow) -> Self {
+        use super::JobError;
+
+        Self {
+            id: row.id,
+            name: row.name,
+            status: row.status,
+            project: row.project,
+            started_at: row.started_at,
+            ended_at: row.ended_at,
+            commands: row.commands,
+            error: row.error_command.map(|command| JobError {
+                command,
+                exit_code: row.error_exit_code,
+                signal_terminated: row.error_signal_terminated.unwrap_or(false),
+                message: row.error_message.unwrap_or_default(),
+            }),
+        }
+    }
+}
+
+/// The read path for every `Job`: a left join against `errors` so a failed
+/// job's cause rides along with the row instead of needing a second query.
+pub async fn jobs_with_errors(context: &crate::Context) -> Result<Vec<super::Job>, crate::SubiloError> {
+    use crate::{database, SubiloError};
+
+    let rows: Vec<JobWithErrorRow> = context
+        .database
+        .send(database::Query {
+            query: JOBS_WITH_ERRORS.to_owned(),
+            params: vec![],
+        })
+        .await
+        .map_err(|err| SubiloError::DatabaseActor { source: err })?
+        .map_err(|err| SubiloError::DatabaseQuery { source: err })?;
+
+    Ok(rows.into_iter().map(super::Job::from).collect())
+}
+
+pub async fn job_with_errors(
+    context: &crate::Context,
+    id: &str,
+) -> Result<Option<super::Job>, crate::SubiloError> {
+    use crate::{database, SubiloError};
+
+    let rows: Vec<JobWithErrorRow> = context
+        .database
+        .send(database::Query {
+            query: JOB_WITH_ERRORS_BY_ID.to_owned(),
+            params: vec![id.to_owned()],
+        })
+        .await
+        .map_err(|err| SubiloError::DatabaseActor { source: err })?
+        .map_err(|err| SubiloError::DatabaseQuery { source: err })?;
+
+    Ok(rows.into_iter().next().map(super::Job::from))
+}
@@ -0,0 +1,227 @@
+use nanoid::nanoid;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::time;
+
+use super::{now, query, JobStatus, Witness};
+use crate::core;
+use crate::database;
+use crate::notifier::JobEvent;
+use crate::Context;
+use crate::SubiloError;
+
+pub struct QueueConfig {
+    pub max_concurrent_jobs: usize,
+    pub heartbeat_timeout: Duration,
+    pub sweep_interval: Duration,
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_jobs: 1,
+            heartbeat_timeout: Duration::from_secs(60),
+            sweep_interval: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ClaimedJob {
+    pub id: String,
+    pub name: String,
+    pub project: String,
+    pub commands: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrphanedJob {
+    id: String,
+    name: String,
+    project: String,
+}
+
+pub async fn enqueue(
+    job_name: String,
+    project: &core::Project,
+    context: &Context,
+) -> Result<String, SubiloError> {
+    let id = nanoid!();
+    let status = JobStatus::Queued.to_string().to_lowercase();
+    let commands = project
+        .commands_to_json()
+        .map_err(|err| SubiloError::ParseProjectCommands { source: err })?;
+
+    let queued_at = now();
+
+    context
+        .database
+        .send(database::Execute {
+            query: query::INSERT_JOB.to_owned(),
+            params: vec![
+                id.clone(),
+                job_name.clone(),
+                status,
+                project.name.clone(),
+                commands,
+                queued_at.clone(),
+                queued_at,
+            ],
+        })
+        .await
+        .map_err(|err| SubiloError::DatabaseActor { source: err })?
+        .map_err(|err| SubiloError::DatabaseQuery { source: err })?;
+
+    notify_queued(context, &id, &job_name, &project.name).await;
+
+    Ok(id)
+}
+
+async fn notify_queued(context: &Context, job_id: &str, job_name: &str, project_name: &str) {
+    if context.notifiers.is_empty() {
+        return;
+    }
+
+    let event = JobEvent {
+        job_id: job_id.to_owned(),
+        job_name: job_name.to_owned(),
+        project: project_name.to_owned(),
+        status: JobStatus::Queued,
+        duration_ms: 0,
+        exit_code: None,
+        log_tail: String::new(),
+    };
+
+    for notifier in context.notifiers.iter() {
+        if !notifier.notify_on().contains(&event.status) {
+            continue;
+        }
+
+        if let Err(err) = notifier.notify(&event).await {
+            eprintln!("failed to send job notification: {}", err);
+        }
+    }
+}
+
+pub async fn claim_next(context: &Context) -> Result<Option<ClaimedJob>, SubiloError> {
+    let queued = JobStatus::Queued.to_string().to_lowercase();
+    let started = JobStatus::Started.to_string().to_lowercase();
+
+    let claimed: Option<ClaimedJob> = context
+        .database
+        .send(database::Query {
+            query: query::CLAIM_NEXT_QUEUED_JOB.to_owned(),
+            params: vec![queued, started, now()],
+        })
+        .await
+        .map_err(|err| SubiloError::DatabaseActor { source: err })?
+        .map_err(|err| SubiloError::DatabaseQuery { source: err })?;
+
+    Ok(claimed)
+}
+
+pub(crate) async fn heartbeat(context: &Context, job_id: &str) -> Result<(), SubiloError> {
+    context
+        .database
+        .send(database::Execute {
+            query: query::UPDATE_HEARTBEAT.to_owned(),
+            params: vec![job_id.to_owned(), now()],
+        })
+        .await
+        .map_err(|err| SubiloError::DatabaseActor { source: err })?
+        .map_err(|err| SubiloError::DatabaseQuery { source: err })?;
+
+    Ok(())
+}
+
+pub async fn recover_orphaned_jobs(
+    context: &Context,
+    heartbeat_timeout: Duration,
+) -> Result<(), SubiloError> {
+    let started = JobStatus::Started.to_string().to_lowercase();
+    let cutoff = chrono::Utc::now() - chrono::Duration::from_std(heartbeat_timeout).unwrap();
+    let cutoff = cutoff.to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+
+    let orphaned: Vec<OrphanedJob> = context
+        .database
+        .send(database::Query {
+            query: query::SELECT_ORPHANED_JOBS.to_owned(),
+            params: vec![started, cutoff],
+        })
+        .await
+        .map_err(|err| SubiloError::DatabaseActor { source: err })?
+        .map_err(|err| SubiloError::DatabaseQuery { source: err })?;
+
+    for job in orphaned {
+        let mut witness = Witness::attach(job.id, job.name, job.project, context.clone())?;
+        if let Err(err) = witness.report_orphaned() {
+            eprintln!("failed to report orphaned job: {}", err);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn spawn_workers<F, Fut>(context: Context, config: QueueConfig, run_job: F)
+where
+    F: Fn(Witness) -> Fut + Send + Sync + Copy + 'static,
+    Fut: std::future::Future<Output = ()> + Send + 'static,
+{
+    let semaphore = Arc::new(Semaphore::new(config.max_concurrent_jobs));
+
+    {
+        let context = context.clone();
+        let heartbeat_timeout = config.heartbeat_timeout;
+        let sweep_interval = config.sweep_interval;
+        actix::spawn(async move {
+            if let Err(err) = recover_orphaned_jobs(&context, heartbeat_timeout).await {
+                eprintln!("failed to recover orphaned jobs: {}", err);
+            }
+
+            let mut ticker = time::interval(sweep_interval);
+            loop {
+                ticker.tick().await;
+                if let Err(err) = recover_orphaned_jobs(&context, heartbeat_timeout).await {
+                    eprintln!("failed to recover orphaned jobs: {}", err);
+                }
+            }
+        });
+    }
+
+    actix::spawn(async move {
+        loop {
+            let permit = semaphore.clone().acquire_owned().await.unwrap();
+
+            match claim_next(&context).await {
+                Ok(Some(claimed)) => {
+                    let context = context.clone();
+
+                    let witness = match Witness::resume(claimed, context.clone()) {
+                        Ok(witness) => witness,
+                        Err(err) => {
+                            eprintln!("failed to resume claimed job: {}", err);
+                            drop(permit);
+                            continue;
+                        }
+                    };
+
+                    actix::spawn(async move {
+                        run_job(witness).await;
+                        drop(permit);
+                    });
+                }
+                Ok(None) => {
+                    drop(permit);
+                    time::sleep(Duration::from_secs(1)).await;
+                }
+                Err(err) => {
+                    drop(permit);
+                    eprintln!("failed to claim queued job: {}", err);
+                    time::sleep(Duration::from_secs(1)).await;
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,86 @@
+use async_trait::async_trait;
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use super::{JobEvent, Notifier, NotifierError, NOTIFIER_TIMEOUT};
+use crate::job::JobStatus;
+
+pub struct EmailNotifier {
+    from: String,
+    to: String,
+    notify_on: Vec<JobStatus>,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl EmailNotifier {
+    pub fn new(
+        smtp_host: &str,
+        username: String,
+        password: String,
+        from: String,
+        to: String,
+        notify_on: Vec<JobStatus>,
+    ) -> Result<Self, NotifierError> {
+        let transport = AsyncSmtpTransport::<Tokio1Executor>::relay(smtp_host)
+            .map_err(|err| NotifierError::Email {
+                message: err.to_string(),
+            })?
+            .credentials(Credentials::new(username, password))
+            .timeout(Some(NOTIFIER_TIMEOUT))
+            .build();
+
+        Ok(Self {
+            from,
+            to,
+            notify_on,
+            transport,
+        })
+    }
+}
+
+fn subject(event: &JobEvent) -> String {
+    format!(
+        "[subilo] {} {} on {}",
+        event.job_name, event.status, event.project
+    )
+}
+
+fn body(event: &JobEvent) -> String {
+    format!(
+        "job: {}\nproject: {}\nstatus: {}\nduration: {}ms\nexit code: {:?}\n\n{}",
+        event.job_name, event.project, event.status, event.duration_ms, event.exit_code, event.log_tail
+    )
+}
+
+#[async_trait]
+impl Notifier for EmailNotifier {
+    fn notify_on(&self) -> &[JobStatus] {
+        &self.notify_on
+    }
+
+    async fn notify(&self, event: &JobEvent) -> Result<(), NotifierError> {
+        let message = Message::builder()
+            .from(self.from.parse().map_err(|_| NotifierError::Email {
+                message: "invalid from address".to_owned(),
+            })?)
+            .to(self.to.parse().map_err(|_| NotifierError::Email {
+                message: "invalid to address".to_owned(),
+            })?)
+            .header(ContentType::TEXT_PLAIN)
+            .subject(subject(event))
+            .body(body(event))
+            .map_err(|err| NotifierError::Email {
+                message: err.to_string(),
+            })?;
+
+        self.transport
+            .send(message)
+            .await
+            .map_err(|err| NotifierError::Email {
+                message: err.to_string(),
+            })?;
+
+        Ok(())
+    }
+}
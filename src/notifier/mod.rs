@@ -0,0 +1,53 @@
+use async_trait::async_trait;
+use std::time::Duration;
+
+use crate::job::JobStatus;
+
+pub mod email;
+pub mod slack;
+pub mod webhook;
+
+pub use email::EmailNotifier;
+pub use slack::SlackNotifier;
+pub use webhook::WebhookNotifier;
+
+/// Caps how long a slow or unreachable notifier endpoint can hold up job
+/// reporting, which waits for every notification it sends.
+pub(crate) const NOTIFIER_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone)]
+pub struct JobEvent {
+    pub job_id: String,
+    pub job_name: String,
+    pub project: String,
+    pub status: JobStatus,
+    pub duration_ms: i64,
+    pub exit_code: Option<i32>,
+    pub log_tail: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum NotifierError {
+    #[error("failed to send notification: {source}")]
+    Request {
+        #[from]
+        source: reqwest::Error,
+    },
+
+    #[error("failed to send email notification: {message}")]
+    Email { message: String },
+}
+
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    fn notify_on(&self) -> &[JobStatus] {
+        &[
+            JobStatus::Queued,
+            JobStatus::Started,
+            JobStatus::Succeeded,
+            JobStatus::Failed,
+        ]
+    }
+
+    async fn notify(&self, event: &JobEvent) -> Result<(), NotifierError>;
+}
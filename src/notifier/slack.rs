@@ -0,0 +1,69 @@
+use async_trait::async_trait;
+use serde::Serialize;
+
+use super::{JobEvent, Notifier, NotifierError, NOTIFIER_TIMEOUT};
+use crate::job::JobStatus;
+
+pub struct SlackNotifier {
+    webhook_url: String,
+    notify_on: Vec<JobStatus>,
+    client: reqwest::Client,
+}
+
+impl SlackNotifier {
+    pub fn new(webhook_url: String, notify_on: Vec<JobStatus>) -> Result<Self, NotifierError> {
+        let client = reqwest::Client::builder()
+            .timeout(NOTIFIER_TIMEOUT)
+            .build()?;
+
+        Ok(Self {
+            webhook_url,
+            notify_on,
+            client,
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct SlackMessage {
+    text: String,
+}
+
+fn format_message(event: &JobEvent) -> String {
+    match event.status {
+        JobStatus::Queued => format!("⏳ *{}* queued for `{}`", event.job_name, event.project),
+        JobStatus::Started => format!(
+            "▶️ *{}* started on `{}`",
+            event.job_name, event.project
+        ),
+        JobStatus::Succeeded => format!(
+            "✅ *{}* succeeded on `{}` in {}ms",
+            event.job_name, event.project, event.duration_ms
+        ),
+        JobStatus::Failed => format!(
+            "❌ *{}* failed on `{}` (exit {:?}) in {}ms\n```\n{}\n```",
+            event.job_name, event.project, event.exit_code, event.duration_ms, event.log_tail
+        ),
+    }
+}
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    fn notify_on(&self) -> &[JobStatus] {
+        &self.notify_on
+    }
+
+    async fn notify(&self, event: &JobEvent) -> Result<(), NotifierError> {
+        let message = SlackMessage {
+            text: format_message(event),
+        };
+
+        self.client
+            .post(&self.webhook_url)
+            .json(&message)
+            .send()
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use async_trait::async_trait;
+use serde::Serialize;
+
+use super::{JobEvent, Notifier, NotifierError, NOTIFIER_TIMEOUT};
+use crate::job::JobStatus;
+
+pub struct WebhookNotifier {
+    url: String,
+    notify_on: Vec<JobStatus>,
+    client: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: String, notify_on: Vec<JobStatus>) -> Result<Self, NotifierError> {
+        let client = reqwest::Client::builder()
+            .timeout(NOTIFIER_TIMEOUT)
+            .build()?;
+
+        Ok(Self {
+            url,
+            notify_on,
+            client,
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct WebhookPayload<'a> {
+    job_id: &'a str,
+    job_name: &'a str,
+    project: &'a str,
+    status: String,
+    duration_ms: i64,
+    exit_code: Option<i32>,
+    log_tail: &'a str,
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    fn notify_on(&self) -> &[JobStatus] {
+        &self.notify_on
+    }
+
+    async fn notify(&self, event: &JobEvent) -> Result<(), NotifierError> {
+        let payload = WebhookPayload {
+            job_id: &event.job_id,
+            job_name: &event.job_name,
+            project: &event.project,
+            status: event.status.to_string(),
+            duration_ms: event.duration_ms,
+            exit_code: event.exit_code,
+            log_tail: &event.log_tail,
+        };
+
+        self.client.post(&self.url).json(&payload).send().await?;
+
+        Ok(())
+    }
+}
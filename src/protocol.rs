@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+use crate::job::{JobStatus, Witness};
+use crate::SubiloError;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Message {
+    StartJob {
+        project: String,
+        commands: serde_json::Value,
+    },
+    CommandStarted {
+        command: String,
+    },
+    CommandOutput {
+        chunk: String,
+    },
+    CommandExited {
+        code: Option<i32>,
+    },
+    JobFinished {
+        status: JobStatus,
+    },
+    Ping,
+    Pong,
+}
+
+impl Message {
+    pub fn to_line(&self) -> Result<String, serde_json::Error> {
+        Ok(format!("{}\n", serde_json::to_string(self)?))
+    }
+
+    pub fn from_line(line: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(line.trim_end())
+    }
+}
+
+pub fn apply_to_witness(witness: &mut Witness, message: Message) -> Result<(), SubiloError> {
+    match message {
+        Message::CommandStarted { command } => witness.report_command(&command),
+        Message::CommandOutput { chunk } => {
+            let mut log = witness
+                .try_clone_log()
+                .map_err(|err| SubiloError::WriteLogFile { source: err })?;
+
+            log.write_all(chunk.as_bytes())
+                .map_err(|err| SubiloError::WriteLogFile { source: err })
+        }
+        Message::CommandExited { code } => match code {
+            Some(0) => Ok(()),
+            code => witness.report_command_error_by_code(code),
+        },
+        Message::JobFinished { status } if status == JobStatus::Succeeded => {
+            witness.report_command_success()
+        }
+        Message::JobFinished { .. } | Message::StartJob { .. } | Message::Ping | Message::Pong => {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use std::process::Stdio;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::process::Command;
+use tokio::sync::mpsc;
+
+use crate::job::JobStatus;
+use crate::protocol::Message;
+use crate::SubiloError;
+
+pub async fn connect(driver_addr: &str, token: &str) -> Result<(), SubiloError> {
+    let stream = TcpStream::connect(driver_addr)
+        .await
+        .map_err(|err| SubiloError::RunnerConnect { source: err })?;
+
+    let (read_half, mut write_half) = stream.into_split();
+    let mut lines = BufReader::new(read_half).lines();
+
+    write_half
+        .write_all(format!("Bearer {}\n", token).as_bytes())
+        .await
+        .map_err(|err| SubiloError::RunnerWrite { source: err })?;
+
+    while let Some(line) = lines
+        .next_line()
+        .await
+        .map_err(|err| SubiloError::RunnerRead { source: err })?
+    {
+        let message = Message::from_line(&line)
+            .map_err(|err| SubiloError::RunnerParseMessage { source: err })?;
+
+        match message {
+            Message::StartJob { commands, .. } => {
+                run_commands(commands, &mut write_half).await?;
+            }
+            Message::Ping => {
+                send(&mut write_half, &Message::Pong).await?;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_commands(
+    commands: serde_json::Value,
+    write_half: &mut tokio::net::tcp::OwnedWriteHalf,
+) -> Result<(), SubiloError> {
+    let commands: Vec<String> = serde_json::from_value(commands)
+        .map_err(|err| SubiloError::RunnerParseMessage { source: err })?;
+
+    for command in commands {
+        send(
+            write_half,
+            &Message::CommandStarted {
+                command: command.clone(),
+            },
+        )
+        .await?;
+
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(&command)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| SubiloError::RunnerSpawnCommand { source: err })?;
+
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let stderr = child.stderr.take().expect("stderr is piped");
+
+        let (tx, mut rx) = mpsc::channel::<String>(32);
+
+        let stdout_tx = tx.clone();
+        let stdout_task = tokio::spawn(async move {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Ok(Some(chunk)) = lines.next_line().await {
+                if stdout_tx.send(chunk).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let stderr_task = tokio::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Ok(Some(chunk)) = lines.next_line().await {
+                if tx.send(chunk).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        while let Some(chunk) = rx.recv().await {
+            send(write_half, &Message::CommandOutput { chunk }).await?;
+        }
+
+        stdout_task
+            .await
+            .map_err(|err| SubiloError::RunnerReadTask { source: err })?;
+        stderr_task
+            .await
+            .map_err(|err| SubiloError::RunnerReadTask { source: err })?;
+
+        let status = child
+            .wait()
+            .await
+            .map_err(|err| SubiloError::RunnerWaitCommand { source: err })?;
+
+        send(
+            write_half,
+            &Message::CommandExited {
+                code: status.code(),
+            },
+        )
+        .await?;
+
+        if !status.success() {
+            send(
+                write_half,
+                &Message::JobFinished {
+                    status: JobStatus::Failed,
+                },
+            )
+            .await?;
+
+            return Ok(());
+        }
+    }
+
+    send(
+        write_half,
+        &Message::JobFinished {
+            status: JobStatus::Succeeded,
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+async fn send(
+    write_half: &mut tokio::net::tcp::OwnedWriteHalf,
+    message: &Message,
+) -> Result<(), SubiloError> {
+    let line = message
+        .to_line()
+        .map_err(|err| SubiloError::RunnerParseMessage { source: err })?;
+
+    write_half
+        .write_all(line.as_bytes())
+        .await
+        .map_err(|err| SubiloError::RunnerWrite { source: err })
+}
@@ -0,0 +1,52 @@
+use rustls::{Certificate, PrivateKey, ServerConfig};
+use rustls_pemfile::{certs, pkcs8_private_keys, rsa_private_keys};
+use std::fs::File;
+use std::io::{BufReader, Cursor};
+
+use crate::SubiloError;
+
+// openssl req -x509 -newkey rsa:4096 -nodes -keyout key.pem -out cert.pem -days 365 -subj "/CN=localhost"
+// (works with both "BEGIN PRIVATE KEY" and "BEGIN RSA PRIVATE KEY" output)
+pub fn load_rustls_config(cert_path: &str, key_path: &str) -> Result<ServerConfig, SubiloError> {
+    let cert_file =
+        File::open(cert_path).map_err(|err| SubiloError::ReadTlsCert { source: err })?;
+    let key_bytes =
+        std::fs::read(key_path).map_err(|err| SubiloError::ReadTlsKey { source: err })?;
+
+    let cert_chain = certs(&mut BufReader::new(cert_file))
+        .map_err(|err| SubiloError::ParseTlsCert { source: err })?
+        .into_iter()
+        .map(Certificate)
+        .collect();
+
+    let mut keys = pkcs8_private_keys(&mut Cursor::new(&key_bytes))
+        .map_err(|err| SubiloError::ParseTlsKey { source: err })?;
+
+    if keys.is_empty() {
+        keys = rsa_private_keys(&mut Cursor::new(&key_bytes))
+            .map_err(|err| SubiloError::ParseTlsKey { source: err })?;
+    }
+
+    let key = keys.pop().ok_or(SubiloError::MissingTlsKey).map(PrivateKey)?;
+
+    ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(cert_chain, key)
+        .map_err(|err| SubiloError::BuildTlsConfig { source: err })
+}
+
+/// Resolves the `--tls-cert`/`--tls-key` CLI flags into a `ServerConfig`
+/// the bootstrap can pass to `HttpServer::bind_rustls`. `None` means plain
+/// HTTP; only one of the two flags being set is a configuration error
+/// rather than a silent fallback to HTTP.
+pub fn resolve(
+    cert_path: Option<&str>,
+    key_path: Option<&str>,
+) -> Result<Option<ServerConfig>, SubiloError> {
+    match (cert_path, key_path) {
+        (Some(cert_path), Some(key_path)) => load_rustls_config(cert_path, key_path).map(Some),
+        (None, None) => Ok(None),
+        _ => Err(SubiloError::IncompleteTlsConfig),
+    }
+}